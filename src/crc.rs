@@ -8,6 +8,7 @@ pub struct CrcConfig {
     pub reflect_input: BitReversal,
     pub reflect_output: bool,
     pub initial_value: u32,
+    pub xorout: u32,
     pub polynomial: Polynomial,
 }
 
@@ -67,6 +68,30 @@ impl Polynomial {
     }
 }
 
+/// A well-known, named CRC model with its published `check` constant: the CRC of the nine ASCII
+/// bytes `b"123456789"`. Used to validate both implementations against an external ground truth.
+pub struct CrcModel {
+    pub name: &'static str,
+    pub polynomial: Polynomial,
+    pub init: u32,
+    pub reflect_input: BitReversal,
+    pub reflect_output: bool,
+    pub xorout: u32,
+    pub check: u32,
+}
+
+impl CrcModel {
+    pub fn config(&self) -> CrcConfig {
+        CrcConfig {
+            reflect_input: self.reflect_input,
+            reflect_output: self.reflect_output,
+            initial_value: self.init,
+            xorout: self.xorout,
+            polynomial: self.polynomial,
+        }
+    }
+}
+
 pub struct CrcCalculation {
     pub config: CrcConfig,
     pub steps: Vec<Step>,
@@ -76,13 +101,68 @@ pub enum Step {
     Data8(u8),
     Data16(u16),
     Data32(u32),
+    Slice(Vec<u8>),
 }
 
 mod hardware {
     use super::*;
 
+    // CRC data register, as a fixed DMA destination address.
+    const CRC_DR: u32 = 0x4002_3000;
+
     impl CrcCalculation {
         pub fn run_hardware(&self, crc: &mut stm32::CRC) -> u32 {
+            self.configure(crc);
+
+            for step in &self.steps {
+                match step {
+                    Step::Data8(value) => crc.dr8().write(|w| w.dr8().bits(*value)),
+                    Step::Data16(value) => crc.dr16().write(|w| w.dr16().bits(*value)),
+                    Step::Data32(value) => crc.dr().write(|w| w.dr().bits(*value)),
+                    Step::Slice(values) => {
+                        for value in values {
+                            crc.dr8().write(|w| w.dr8().bits(*value));
+                        }
+                    }
+                }
+            }
+
+            self.finalize(crc)
+        }
+
+        /// Feed the input through the peripheral using a DMA transfer (in memory-to-memory mode)
+        /// into the data register rather than one CPU write per word.
+        pub fn run_hardware_dma(&self, crc: &mut stm32::CRC, dma_channel: &mut stm32::DMA1) -> u32 {
+            self.configure(crc);
+
+            let bytes = self.byte_stream();
+            let ch = &dma_channel.ch1;
+
+            // The CRC peripheral has no DMA request line, so the transfer is driven in
+            // memory-to-memory mode: incrementing source bytes are streamed into the fixed data
+            // register.
+            ch.par.write(|w| w.pa().bits(bytes.as_ptr() as u32));
+            ch.mar.write(|w| w.ma().bits(CRC_DR));
+            ch.ndtr.write(|w| w.ndt().bits(bytes.len() as u16));
+            ch.cr.write(|w| w
+                .mem2mem().set_bit()
+                .pinc().set_bit()
+                .minc().clear_bit()
+                .psize().bits8()
+                .msize().bits8()
+                .en().set_bit()
+            );
+
+            // Wait for transfer-complete, then acknowledge the flag and disable the channel.
+            while dma_channel.isr.read().tcif1().bit_is_clear() {}
+            dma_channel.ifcr.write(|w| w.ctcif1().set_bit());
+            ch.cr.modify(|_, w| w.en().clear_bit());
+
+            self.finalize(crc)
+        }
+
+        /// Program the init value, polynomial and reflection settings, and reset the unit.
+        fn configure(&self, crc: &mut stm32::CRC) {
             crc.init.write(|w| w.init().bits(self.config.initial_value));
 
             // Current version of stm32f0 crate doesn't yet support the `pol` register, so we write the
@@ -96,15 +176,31 @@ mod hardware {
                 .polysize().variant(self.config.polynomial.poly_size())
                 .reset().set_bit()
             );
+        }
 
+        /// Read the result, emulating the missing hardware xorout by XORing with `xorout` masked
+        /// to the polynomial width.
+        fn finalize(&self, crc: &mut stm32::CRC) -> u32 {
+            let mask = match self.config.polynomial.bits() {
+                32 => u32::MAX,
+                bits => (1u32 << bits) - 1,
+            };
+            crc.dr().read().bits() ^ (self.config.xorout & mask)
+        }
+
+        /// Flatten the steps into a byte stream for DMA feeding; multi-byte words contribute their
+        /// big-endian bytes, matching the software reference's `digest` order.
+        fn byte_stream(&self) -> Vec<u8> {
+            let mut bytes = Vec::new();
             for step in &self.steps {
                 match step {
-                    Step::Data8(value) => crc.dr8().write(|w| w.dr8().bits(*value)),
-                    Step::Data16(value) => crc.dr16().write(|w| w.dr16().bits(*value)),
-                    Step::Data32(value) => crc.dr().write(|w| w.dr().bits(*value)),
+                    Step::Data8(value) => bytes.push(*value),
+                    Step::Data16(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+                    Step::Data32(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+                    Step::Slice(values) => bytes.extend_from_slice(values),
                 }
             }
-            crc.dr().read().bits()
+            bytes
         }
     }
 }
@@ -128,15 +224,28 @@ mod software {
                     Step::Data8(value) => crc.digest(&input_reversal.reflect8(*value).to_be_bytes()),
                     Step::Data16(value) => crc.digest(&input_reversal.reflect16(*value).to_be_bytes()),
                     Step::Data32(value) => crc.digest(&input_reversal.reflect32(*value).to_be_bytes()),
+                    Step::Slice(values) => {
+                        for value in values {
+                            crc.digest(&[input_reversal.reflect8(*value)]);
+                        }
+                    }
                 }
             }
 
-            let result = crc.get_crc();
-            if self.config.reflect_output {
-                self.config.polynomial.reflect_output(result)
+            let raw = crc.get_crc();
+            let reflected = if self.config.reflect_output {
+                self.config.polynomial.reflect_output(raw)
             } else {
-                result
-            }
+                raw
+            };
+
+            // The final XOR is applied after output reflection, matching the hardware path which
+            // reflects via `rev_out` and only then XORs with `xorout`.
+            let mask = match self.config.polynomial.bits() {
+                32 => u32::MAX,
+                bits => (1u32 << bits) - 1,
+            };
+            reflected ^ (self.config.xorout & mask)
         }
     }
 
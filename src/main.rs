@@ -6,22 +6,28 @@
 
 extern crate alloc;
 
-use alloc::{format, vec, vec::Vec};
+use alloc::{format, string::String, vec, vec::Vec};
+use embedded_hal::serial::Read;
 use alloc_cortex_m::CortexMHeap;
 use core::alloc::Layout;
 use core::fmt::Write;
+use cortex_m::peripheral::SYST;
+use cortex_m::peripheral::syst::SystClkSource;
 use cortex_m_rt::entry;
 use panic_halt as _;
 use stm32f0xx_hal::{stm32, prelude::*};
 use stm32f0xx_hal::serial::Serial;
 use strum::IntoEnumIterator;
 
-use crate::crc::{BitReversal, CrcCalculation, CrcConfig, Polynomial, Step};
+use crate::crc::{BitReversal, CrcCalculation, CrcConfig, CrcModel, Polynomial, Step};
 
 mod crc;
 
 const HEAP_SIZE: usize = 2048;
 
+// SysTick is a 24-bit down-counter; run it free-running over its full range.
+const SYST_RELOAD: u32 = 0x00FF_FFFF;
+
 #[global_allocator]
 static ALLOCATOR: CortexMHeap = CortexMHeap::empty();
 
@@ -39,6 +45,22 @@ static INITIAL_VALUES: [u32; 3] = [
     0xFFFFFFFF,
     0x000000FF,
 ];
+static XOROUT_VALUES: [u32; 3] = [
+    0x00000000,
+    0xFFFFFFFF,
+    // Non-palindromic: exercises the ordering of xorout relative to output reflection.
+    0x000000FF,
+];
+
+// Well-known CRC models, each with its published check value (the CRC of b"123456789").
+static MODELS: [CrcModel; 6] = [
+    CrcModel { name: "CRC-8/SMBUS",       polynomial: Polynomial::Crc8(0x07),       init: 0x00000000, reflect_input: BitReversal::Disabled, reflect_output: false, xorout: 0x00000000, check: 0xF4 },
+    CrcModel { name: "CRC-7/MMC",         polynomial: Polynomial::Crc7(0x09),       init: 0x00000000, reflect_input: BitReversal::Disabled, reflect_output: false, xorout: 0x00000000, check: 0x75 },
+    CrcModel { name: "CRC-16/CCITT-FALSE", polynomial: Polynomial::Crc16(0x1021),   init: 0x0000FFFF, reflect_input: BitReversal::Disabled, reflect_output: false, xorout: 0x00000000, check: 0x29B1 },
+    CrcModel { name: "CRC-16/MODBUS",     polynomial: Polynomial::Crc16(0x8005),    init: 0x0000FFFF, reflect_input: BitReversal::By8Bits,  reflect_output: true,  xorout: 0x00000000, check: 0x4B37 },
+    CrcModel { name: "CRC-32/ISO-HDLC",   polynomial: Polynomial::Crc32(0x04C11DB7), init: 0xFFFFFFFF, reflect_input: BitReversal::By8Bits,  reflect_output: true,  xorout: 0xFFFFFFFF, check: 0xCBF43926 },
+    CrcModel { name: "CRC-32/BZIP2",      polynomial: Polynomial::Crc32(0x04C11DB7), init: 0xFFFFFFFF, reflect_input: BitReversal::Disabled, reflect_output: false, xorout: 0xFFFFFFFF, check: 0xFC891918 },
+];
 
 fn steps() -> Vec<Vec<Step>> {
     vec![
@@ -51,6 +73,19 @@ fn steps() -> Vec<Vec<Step>> {
         vec![Step::Data32(0x423268A4), Step::Data32(0xAD91FE38)],
     ]
 }
+// Benchmark vectors, including larger buffers so the per-byte cost is meaningful.
+fn bench_steps() -> Vec<Vec<Step>> {
+    fn buffer(len: u32) -> Vec<u8> {
+        (0..len).map(|i| (i.wrapping_mul(31).wrapping_add(7)) as u8).collect()
+    }
+    vec![
+        vec![Step::Data8(0x42)],
+        vec![Step::Data32(0x423268A4)],
+        vec![Step::Slice(buffer(64))],
+        vec![Step::Slice(buffer(256))],
+        vec![Step::Slice(buffer(1024))],
+    ]
+}
 // ***********************************************************************************************
 
 #[entry]
@@ -59,9 +94,17 @@ fn main() -> ! {
         init_allocator();
 
         let mut dp = stm32::Peripherals::take().unwrap();
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+
+        // The Cortex-M0 core has no DWT cycle counter, so use SysTick as a free-running,
+        // core-clock-rate time source for the benchmarking mode.
+        cp.SYST.set_clock_source(SystClkSource::Core);
+        cp.SYST.set_reload(SYST_RELOAD);
+        cp.SYST.clear_current();
+        cp.SYST.enable_counter();
 
-        // Enable CRC clock
-        dp.RCC.ahbenr.modify(|_, w| w.crcen().enabled());
+        // Enable CRC and DMA clocks
+        dp.RCC.ahbenr.modify(|_, w| w.crcen().enabled().dmaen().enabled());
         dp.RCC.apb2enr.modify(|_, w| w.usart1en().enabled());
         let mut rcc = dp.RCC.configure().freeze(&mut dp.FLASH);
 
@@ -70,11 +113,13 @@ fn main() -> ! {
         let rx = gpioa.pa10.into_alternate_af1(cs);
         let mut serial = Serial::usart1(dp.USART1, (tx, rx), 115_200.bps(), &mut rcc);
         let mut crc = dp.CRC;
+        let mut dma = dp.DMA1;
 
+        run_model_checks(&mut serial, &mut crc);
         run_tests(&mut serial, &mut crc);
-        loop {
-            // NOP
-        }
+        run_dma_test(&mut serial, &mut crc, &mut dma);
+        run_benchmarks(&mut serial, &mut crc);
+        run_console(&mut serial, &mut crc);
     })
 }
 
@@ -83,28 +128,31 @@ fn run_tests<S: Write>(serial: &mut S, crc: &mut stm32::CRC) {
     let mut failed= 0u32;
     serial.write_str(
     "\r\n\
-     Type  | Polynomial | Input refl | Output refl |   Init val | Test |     Output | Result\r\n\
-     ---------------------------------------------------------------------------------------\r\n"
+     Type  | Polynomial | Input refl | Output refl |   Init val |     Xorout | Test |     Output | Result\r\n\
+     ----------------------------------------------------------------------------------------------------\r\n"
     ).unwrap();
     for polynomial in POLYNOMIALS {
         for reflect_input in BitReversal::iter() {
             for reflect_output in [false, true] {
                 for initial_value in INITIAL_VALUES {
-                    for (i, steps) in steps().into_iter().enumerate() {
-                        let calculation = CrcCalculation {
-                            config: CrcConfig {
-                                reflect_input,
-                                reflect_output,
-                                initial_value,
-                                polynomial
-                            },
-                            steps,
-                        };
-
-                        let name = format!("{:>5} | 0x{:08x} | {:>10} | {:>11} | 0x{:08x} | {:>4}",
-                           polynomial, polynomial.value(), reflect_input, to_enabled_disabled(reflect_output), initial_value, i);
-                        let pass = crc_test(serial, crc, &calculation, &name);
-                        if pass { passed += 1; } else { failed += 1; }
+                    for xorout in XOROUT_VALUES {
+                        for (i, steps) in steps().into_iter().enumerate() {
+                            let calculation = CrcCalculation {
+                                config: CrcConfig {
+                                    reflect_input,
+                                    reflect_output,
+                                    initial_value,
+                                    xorout,
+                                    polynomial
+                                },
+                                steps,
+                            };
+
+                            let name = format!("{:>5} | 0x{:08x} | {:>10} | {:>11} | 0x{:08x} | 0x{:08x} | {:>4}",
+                               polynomial, polynomial.value(), reflect_input, to_enabled_disabled(reflect_output), initial_value, xorout, i);
+                            let pass = crc_test(serial, crc, &calculation, &name);
+                            if pass { passed += 1; } else { failed += 1; }
+                        }
                     }
                 }
             }
@@ -114,6 +162,229 @@ fn run_tests<S: Write>(serial: &mut S, crc: &mut stm32::CRC) {
     serial.write_fmt(format_args!("test result: {}. {} passed; {} failed\r\n", result, passed, failed)).unwrap();
 }
 
+fn run_model_checks<S: Write>(serial: &mut S, crc: &mut stm32::CRC) {
+    let mut passed = 0u32;
+    let mut failed = 0u32;
+    serial.write_str(
+    "\r\n\
+     Model               |      Check |   Software |   Hardware | Result\r\n\
+     ----------------------------------------------------------------------\r\n"
+    ).unwrap();
+    for model in &MODELS {
+        let steps = b"123456789".iter().map(|b| Step::Data8(*b)).collect();
+        let calculation = CrcCalculation { config: model.config(), steps };
+        let software = calculation.run_software();
+        let hardware = calculation.run_hardware(crc);
+        let pass = software == model.check && hardware == model.check;
+        serial.write_fmt(format_args!("{:<19} | 0x{:08x} | 0x{:08x} | 0x{:08x} | {}\r\n",
+            model.name, model.check, software, hardware,
+            if pass { "OK" } else { "FAILED" })).unwrap();
+        if pass { passed += 1; } else { failed += 1; }
+    }
+    let result = if failed == 0 { "ok" } else { "FAILED" };
+    serial.write_fmt(format_args!("model check result: {}. {} passed; {} failed\r\n", result, passed, failed)).unwrap();
+}
+
+fn run_dma_test<S: Write>(serial: &mut S, crc: &mut stm32::CRC, dma: &mut stm32::DMA1) {
+    // A multi-hundred-byte buffer, far larger than the CPU-write test vectors.
+    let buffer: Vec<u8> = (0..512u32).map(|i| (i.wrapping_mul(31).wrapping_add(7)) as u8).collect();
+    let config = CrcConfig {
+        reflect_input: BitReversal::By8Bits,
+        reflect_output: true,
+        initial_value: 0xFFFFFFFF,
+        xorout: 0xFFFFFFFF,
+        polynomial: Polynomial::Crc32(0x04C11DB7),
+    };
+
+    let cpu = CrcCalculation {
+        config: config.clone(),
+        steps: vec![Step::Slice(buffer.clone())],
+    };
+    let dma_calc = CrcCalculation {
+        config,
+        steps: vec![Step::Slice(buffer)],
+    };
+
+    let cpu_output = cpu.run_hardware(crc);
+    let dma_output = dma_calc.run_hardware_dma(crc, dma);
+    let result = if cpu_output == dma_output { "OK" } else { "FAILED" };
+    serial.write_fmt(format_args!(
+        "\r\nDMA test: cpu 0x{:08x} | dma 0x{:08x} | {}\r\n",
+        cpu_output, dma_output, result
+    )).unwrap();
+}
+
+fn step_len(steps: &[Step]) -> usize {
+    steps.iter().map(|step| match step {
+        Step::Data8(_) => 1,
+        Step::Data16(_) => 2,
+        Step::Data32(_) => 4,
+        Step::Slice(values) => values.len(),
+    }).sum()
+}
+
+/// Number of SysTick ticks elapsed between two snapshots, accounting for the 24-bit down-count.
+fn syst_elapsed(start: u32, end: u32) -> u32 {
+    start.wrapping_sub(end) & SYST_RELOAD
+}
+
+/// Measure the cycle cost of the hardware and software CRC paths for each benchmark vector using
+/// the SysTick counter, reporting the per-byte cost so the hardware/software crossover is visible.
+fn run_benchmarks<S: Write>(serial: &mut S, crc: &mut stm32::CRC) {
+    let config = CrcConfig {
+        reflect_input: BitReversal::By8Bits,
+        reflect_output: true,
+        initial_value: 0xFFFFFFFF,
+        xorout: 0xFFFFFFFF,
+        polynomial: Polynomial::Crc32(0x04C11DB7),
+    };
+    serial.write_str(
+    "\r\n\
+     Bytes |  HW cycles |  SW cycles | HW cy/byte | SW cy/byte\r\n\
+     ------------------------------------------------------------\r\n"
+    ).unwrap();
+    for steps in bench_steps() {
+        let bytes = step_len(&steps);
+        let calculation = CrcCalculation { config: config.clone(), steps };
+
+        let start = SYST::get_current();
+        let _ = calculation.run_hardware(crc);
+        let hw_cycles = syst_elapsed(start, SYST::get_current());
+
+        let start = SYST::get_current();
+        let _ = calculation.run_software();
+        let sw_cycles = syst_elapsed(start, SYST::get_current());
+
+        let divisor = bytes.max(1) as u32;
+        serial.write_fmt(format_args!(
+            "{:>5} | {:>10} | {:>10} | {:>10} | {:>10}\r\n",
+            bytes, hw_cycles, sw_cycles, hw_cycles / divisor, sw_cycles / divisor
+        )).unwrap();
+    }
+}
+
+/// Interactive, line-based CRC console driven from the USART1 RX line.
+///
+/// Commands (one per line):
+///   poly <crc7|crc8|crc16|crc32> <hex>   set the polynomial
+///   init <hex>                           set the initial value
+///   refin <off|by8|by16|by32>            set input bit reversal
+///   refout <on|off>                      set output bit reversal
+///   xorout <hex>                         set the final XOR value
+///   calc <hex-bytes>                     CRC the payload and print the hardware result
+fn run_console<S>(serial: &mut S, crc: &mut stm32::CRC) -> !
+where
+    S: Write + Read<u8>,
+{
+    let mut config = CrcConfig {
+        reflect_input: BitReversal::Disabled,
+        reflect_output: false,
+        initial_value: 0,
+        xorout: 0,
+        polynomial: Polynomial::Crc32(0x04C11DB7),
+    };
+
+    serial.write_str("\r\nCRC console ready. Type `calc <hex>` to compute.\r\n> ").unwrap();
+    let mut line = String::new();
+    loop {
+        let byte = match serial.read() {
+            Ok(b) => b,
+            Err(nb::Error::WouldBlock) => continue,
+            Err(nb::Error::Other(_)) => continue,
+        };
+        match byte {
+            b'\r' | b'\n' => {
+                if !line.is_empty() {
+                    handle_command(serial, crc, &mut config, &line);
+                    line.clear();
+                }
+                serial.write_str("> ").unwrap();
+            }
+            _ => line.push(byte as char),
+        }
+    }
+}
+
+fn handle_command<S: Write>(serial: &mut S, crc: &mut stm32::CRC, config: &mut CrcConfig, line: &str) {
+    let mut parts = line.split_whitespace();
+    let cmd = match parts.next() {
+        Some(c) => c,
+        None => return,
+    };
+    match cmd {
+        "poly" => match (parts.next(), parts.next().and_then(parse_hex)) {
+            (Some(kind), Some(value)) => match set_polynomial(config, kind, value) {
+                Ok(()) => serial.write_str("ok\r\n").unwrap(),
+                Err(()) => serial.write_str("error: unknown polynomial type\r\n").unwrap(),
+            },
+            _ => serial.write_str("error: usage `poly <crc7|crc8|crc16|crc32> <hex>`\r\n").unwrap(),
+        },
+        "init" => match parts.next().and_then(parse_hex) {
+            Some(v) => { config.initial_value = v; serial.write_str("ok\r\n").unwrap(); }
+            None => serial.write_str("error: usage `init <hex>`\r\n").unwrap(),
+        },
+        "xorout" => match parts.next().and_then(parse_hex) {
+            Some(v) => { config.xorout = v; serial.write_str("ok\r\n").unwrap(); }
+            None => serial.write_str("error: usage `xorout <hex>`\r\n").unwrap(),
+        },
+        "refin" => match parts.next() {
+            Some("off") => { config.reflect_input = BitReversal::Disabled; serial.write_str("ok\r\n").unwrap(); }
+            Some("by8") => { config.reflect_input = BitReversal::By8Bits; serial.write_str("ok\r\n").unwrap(); }
+            Some("by16") => { config.reflect_input = BitReversal::By16Bits; serial.write_str("ok\r\n").unwrap(); }
+            Some("by32") => { config.reflect_input = BitReversal::By32Bits; serial.write_str("ok\r\n").unwrap(); }
+            _ => serial.write_str("error: usage `refin <off|by8|by16|by32>`\r\n").unwrap(),
+        },
+        "refout" => match parts.next() {
+            Some("on") => { config.reflect_output = true; serial.write_str("ok\r\n").unwrap(); }
+            Some("off") => { config.reflect_output = false; serial.write_str("ok\r\n").unwrap(); }
+            _ => serial.write_str("error: usage `refout <on|off>`\r\n").unwrap(),
+        },
+        "calc" => match parts.next().and_then(parse_hex_bytes) {
+            Some(bytes) => {
+                let steps = bytes.into_iter().map(Step::Data8).collect();
+                let calculation = CrcCalculation { config: config.clone(), steps };
+                let output = calculation.run_hardware(crc);
+                serial.write_fmt(format_args!("0x{:08x}\r\n", output)).unwrap();
+            }
+            None => serial.write_str("error: usage `calc <hex-bytes>`\r\n").unwrap(),
+        },
+        _ => serial.write_fmt(format_args!("error: unknown command `{}`\r\n", cmd)).unwrap(),
+    }
+}
+
+fn set_polynomial(config: &mut CrcConfig, kind: &str, value: u32) -> Result<(), ()> {
+    config.polynomial = match kind {
+        "crc7" => Polynomial::Crc7(value as u8),
+        "crc8" => Polynomial::Crc8(value as u8),
+        "crc16" => Polynomial::Crc16(value as u16),
+        "crc32" => Polynomial::Crc32(value),
+        _ => return Err(()),
+    };
+    Ok(())
+}
+
+fn parse_hex(s: &str) -> Option<u32> {
+    let s = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    u32::from_str_radix(s, 16).ok()
+}
+
+fn parse_hex_bytes(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    if s.is_empty() || s.len() % 2 != 0 {
+        return None;
+    }
+    let raw = s.as_bytes();
+    let mut bytes = Vec::with_capacity(raw.len() / 2);
+    let mut i = 0;
+    while i < raw.len() {
+        let hi = (raw[i] as char).to_digit(16)?;
+        let lo = (raw[i + 1] as char).to_digit(16)?;
+        bytes.push((hi * 16 + lo) as u8);
+        i += 2;
+    }
+    Some(bytes)
+}
+
 fn to_enabled_disabled(v: bool) -> &'static str {
     if v {
         "Enabled"